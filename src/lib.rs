@@ -1,18 +1,196 @@
-use std::{cmp, f32, mem};
+use std::{f32, mem};
 
 /// A generic 2D software rasterizer.
 pub struct Cloth<T: Target> {
     path: Path,
     target: T,
-    fill: Color,
+    source: Source,
+    fill_rule: FillRule,
+    tolerance: f32,
+    line_width: f32,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    miter_limit: f32,
+    blend_mode: BlendMode,
+    transform: Transform,
+    transform_stack: Vec<Transform>,
+}
+
+/// A 2×3 affine transformation.
+///
+/// A point `(x, y)` maps to `(a·x + c·y + e, b·x + d·y + f)`, matching the canvas matrix layout
+/// `[a, b, c, d, e, f]`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    e: f32,
+    f: f32,
+}
+
+impl Transform {
+    /// The identity transform.
+    pub fn identity() -> Transform {
+        Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 }
+    }
+
+    fn apply(&self, p: Point) -> Point {
+        Point::new(self.a * p.x + self.c * p.y + self.e, self.b * p.x + self.d * p.y + self.f)
+    }
+
+    /// Returns `self` followed, in local coordinates, by `other` (i.e. the matrix product).
+    fn then(&self, other: &Transform) -> Transform {
+        Transform {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+}
+
+/// The operator used to composite newly painted pixels over the existing target.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Both source and destination are cleared to transparent.
+    Clear,
+    /// The source replaces the destination.
+    Src,
+    /// The destination is kept, ignoring the source.
+    Dst,
+    /// The source is composited over the destination.
+    SrcOver,
+    /// The source is composited under the destination.
+    DstOver,
+    /// The source is shown only where the destination is opaque.
+    SrcIn,
+    /// The destination is shown only where the source is opaque.
+    DstIn,
+    /// The source is shown only where the destination is transparent.
+    SrcOut,
+    /// The destination is shown only where the source is transparent.
+    DstOut,
+    /// The source is shown atop the destination, clipped to it.
+    SrcAtop,
+    /// The destination is shown atop the source, clipped to it.
+    DstAtop,
+    /// The non-overlapping regions of source and destination are kept.
+    Xor,
+    /// The source and destination are summed.
+    Add,
+    /// The channels are multiplied.
+    Multiply,
+    /// The complements of the channels are multiplied.
+    Screen,
+    /// Multiply or screen, depending on the destination.
+    Overlay,
+    /// The darker of the two channels is kept.
+    Darken,
+    /// The lighter of the two channels is kept.
+    Lighten,
+    /// The absolute difference of the channels.
+    Difference,
+}
+
+impl BlendMode {
+    /// Returns the Porter-Duff coefficient pair `(Fa, Fb)` for the source and destination,
+    /// given the source and destination alphas. Separable blends composite like `SrcOver`.
+    fn coefficients(self, src_alpha: f32, dst_alpha: f32) -> (f32, f32) {
+        match self {
+            BlendMode::Clear => (0.0, 0.0),
+            BlendMode::Src => (1.0, 0.0),
+            BlendMode::Dst => (0.0, 1.0),
+            BlendMode::SrcOver => (1.0, 1.0 - src_alpha),
+            BlendMode::DstOver => (1.0 - dst_alpha, 1.0),
+            BlendMode::SrcIn => (dst_alpha, 0.0),
+            BlendMode::DstIn => (0.0, src_alpha),
+            BlendMode::SrcOut => (1.0 - dst_alpha, 0.0),
+            BlendMode::DstOut => (0.0, 1.0 - src_alpha),
+            BlendMode::SrcAtop => (dst_alpha, 1.0 - src_alpha),
+            BlendMode::DstAtop => (1.0 - dst_alpha, src_alpha),
+            BlendMode::Xor => (1.0 - dst_alpha, 1.0 - src_alpha),
+            BlendMode::Add => (1.0, 1.0),
+            _ => (1.0, 1.0 - src_alpha),
+        }
+    }
+
+    /// Returns the separable blend function `B(cb, cs)` for this mode, if it is a separable blend.
+    fn separable(self) -> Option<fn(f32, f32) -> f32> {
+        match self {
+            BlendMode::Multiply => Some(|cb, cs| cb * cs),
+            BlendMode::Screen => Some(|cb, cs| cb + cs - cb * cs),
+            BlendMode::Overlay => Some(|cb, cs| {
+                if cb <= 0.5 {
+                    2.0 * cb * cs
+                } else {
+                    1.0 - 2.0 * (1.0 - cb) * (1.0 - cs)
+                }
+            }),
+            BlendMode::Darken => Some(|cb, cs| cb.min(cs)),
+            BlendMode::Lighten => Some(|cb, cs| cb.max(cs)),
+            BlendMode::Difference => Some(|cb, cs| (cb - cs).abs()),
+            _ => None,
+        }
+    }
+}
+
+/// The shape drawn at the open ends of a stroked path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    /// The stroke ends flush with the endpoint.
+    Butt,
+    /// The stroke ends with a semicircle of radius half the line width.
+    Round,
+    /// The stroke ends with a square extending half the line width past the endpoint.
+    Square,
+}
+
+/// The shape drawn where two segments of a stroked path meet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineJoin {
+    /// The outer edges are extended until they meet, clamped by the miter limit.
+    Miter,
+    /// The corner is rounded with an arc of radius half the line width.
+    Round,
+    /// The corner is cut off with a straight edge between the two offsets.
+    Bevel,
+}
+
+/// Determines which regions of a self-intersecting path are considered inside.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    /// A pixel is inside wherever the edge winding number is non-zero.
+    NonZero,
+    /// A pixel is inside wherever it is crossed by an odd number of edges.
+    EvenOdd,
 }
 
 impl<T: Target> Cloth<T> {
     /// Initializes a new rasterizer that will use the given target as its output.
+    ///
+    /// The default fill rule is [`FillRule::EvenOdd`], preserving the behavior of the original
+    /// scanline filler. Call [`Cloth::set_fill_rule`] with [`FillRule::NonZero`] for the winding
+    /// semantics of full vector backends.
     pub fn new(target: T) -> Cloth<T> {
         let path = Path::new();
-        let fill = [0, 0, 0, 255];
-        Cloth { target, path, fill }
+        Cloth {
+            target,
+            path,
+            source: Source::Solid([0, 0, 0, 255]),
+            fill_rule: FillRule::EvenOdd,
+            tolerance: 0.25,
+            line_width: 1.0,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            miter_limit: 10.0,
+            blend_mode: BlendMode::SrcOver,
+            transform: Transform::identity(),
+            transform_stack: Vec::new(),
+        }
     }
 
     /// Decomposes the `Cloth` into its inner `Target`.
@@ -20,9 +198,24 @@ impl<T: Target> Cloth<T> {
         self.target
     }
 
-    /// Sets the active fill color.
+    /// Sets the active fill color as a solid paint source.
     pub fn set_fill(&mut self, fill: Color) {
-        self.fill = fill;
+        self.source = Source::Solid(fill);
+    }
+
+    /// Sets the active paint source, which supplies the color for each covered pixel.
+    pub fn set_source(&mut self, source: Source) {
+        self.source = source;
+    }
+
+    /// Sets the rule used to decide which regions of a self-intersecting path are filled.
+    pub fn set_fill_rule(&mut self, fill_rule: FillRule) {
+        self.fill_rule = fill_rule;
+    }
+
+    /// Sets the operator used to composite painted pixels over the target.
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
     }
 
     /// Starts a new active path.
@@ -37,7 +230,7 @@ impl<T: Target> Cloth<T> {
 
     /// Begins a new sub-path on the active path at the specified point.
     pub fn move_to(&mut self, x: f32, y: f32) {
-        let point = Point::new(x, y);
+        let point = self.transform.apply(Point::new(x, y));
         self.path.start = point;
         self.path.add(Subpath::Move(point));
     }
@@ -45,59 +238,266 @@ impl<T: Target> Cloth<T> {
     /// Adds a straight line to the current sub-path by connecting the sub-path's last point to the
     /// specified point.
     pub fn line_to(&mut self, x: f32, y: f32) {
-        self.path.add(Subpath::Line(Point::new(x, y)));
+        self.path.add(Subpath::Line(self.transform.apply(Point::new(x, y))));
+    }
+
+    /// Adds a quadratic Bézier curve to the current sub-path, using the single control point
+    /// `(cx, cy)` and ending at `(x, y)`.
+    pub fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        self.path.add(Subpath::Quad(
+            self.transform.apply(Point::new(cx, cy)),
+            self.transform.apply(Point::new(x, y)),
+        ));
+    }
+
+    /// Adds a cubic Bézier curve to the current sub-path, using the control points `(c1x, c1y)`
+    /// and `(c2x, c2y)` and ending at `(x, y)`.
+    pub fn bezier_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        self.path.add(Subpath::Cubic(
+            self.transform.apply(Point::new(c1x, c1y)),
+            self.transform.apply(Point::new(c2x, c2y)),
+            self.transform.apply(Point::new(x, y)),
+        ));
+    }
+
+    /// Translates the current transformation matrix by `(tx, ty)`.
+    pub fn translate(&mut self, tx: f32, ty: f32) {
+        self.transform(1.0, 0.0, 0.0, 1.0, tx, ty);
+    }
+
+    /// Scales the current transformation matrix by `(sx, sy)`.
+    pub fn scale(&mut self, sx: f32, sy: f32) {
+        self.transform(sx, 0.0, 0.0, sy, 0.0, 0.0);
+    }
+
+    /// Rotates the current transformation matrix clockwise by `radians`.
+    pub fn rotate(&mut self, radians: f32) {
+        let (sin, cos) = radians.sin_cos();
+        self.transform(cos, sin, -sin, cos, 0.0, 0.0);
+    }
+
+    /// Multiplies the current transformation matrix by the given `[a, b, c, d, e, f]` matrix.
+    pub fn transform(&mut self, a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) {
+        self.transform = self.transform.then(&Transform { a, b, c, d, e, f });
+    }
+
+    /// Pushes the current transformation matrix onto the stack.
+    pub fn save(&mut self) {
+        self.transform_stack.push(self.transform);
+    }
+
+    /// Pops the most recently saved transformation matrix, restoring it as current.
+    pub fn restore(&mut self) {
+        if let Some(transform) = self.transform_stack.pop() {
+            self.transform = transform;
+        }
+    }
+
+    /// Sets the flatness tolerance, in pixels, used when flattening curves into line segments.
+    pub fn set_tolerance(&mut self, tolerance: f32) {
+        self.tolerance = tolerance;
+    }
+
+    /// Sets the width, in pixels, of strokes produced by `stroke`.
+    pub fn set_line_width(&mut self, line_width: f32) {
+        self.line_width = line_width;
+    }
+
+    /// Sets the cap drawn at the open ends of stroked paths.
+    pub fn set_line_cap(&mut self, line_cap: LineCap) {
+        self.line_cap = line_cap;
+    }
+
+    /// Sets the join drawn where segments of a stroked path meet.
+    pub fn set_line_join(&mut self, line_join: LineJoin) {
+        self.line_join = line_join;
+    }
+
+    /// Sets the limit at which a miter join is replaced by a bevel, as a ratio of the miter length
+    /// to half the line width.
+    pub fn set_miter_limit(&mut self, miter_limit: f32) {
+        self.miter_limit = miter_limit;
     }
 
     /// Fills the active path with the active fill color.
+    ///
+    /// The path is flattened into edges which are accumulated into a signed-area coverage buffer;
+    /// sweeping that buffer yields analytic anti-aliasing on every edge, not just horizontal spans.
     pub fn fill(&mut self) {
         self.close_path();
-        let lines = self.path.to_lines();
+        let lines = self.path.to_lines(self.tolerance);
+        self.rasterize(&lines, self.fill_rule);
+    }
+
+    /// Rasterizes a set of outline edges with the active paint source under the given fill rule.
+    fn rasterize(&mut self, lines: &Lines, rule: FillRule) {
+        let width = self.target.width();
+        let height = self.target.height();
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        // Clamp the outline's bounding box to the target so the accumulation buffer is no larger
+        // than the region it can actually touch.
         let bounds = lines.bounds();
-        let mut y = f32::max(0.0, f32::min(bounds.br.y, self.target.height() as f32));
-        while y >= bounds.tl.y {
-            // TODO: The problem with this scanline rasterization method is that lines that exist on
-            // non-integer y-coordinates don't get antialiased:
-            let xs = lines.x_intersections(y + 0.5);
-            for pair in xs.chunks(2).filter(|c| c.len() == 2) {
-                let start = cmp::max(0, pair[0].floor() as u32);
-                let end = cmp::min(pair[1].floor() as u32, self.target.width() - 1);
-                let falpha = u2f(self.fill[3]);
-                let mut start_fill = self.fill;
-                start_fill[3] = f2u(falpha * (1.0 - pair[0].fract()));
-                let mut end_fill = self.fill;
-                end_fill[3] = f2u(falpha * pair[1].fract());
-                for x in start..=end {
-                    self.fill_pixel(x, y as u32, if x == start {
-                        start_fill
-                    } else if x == end {
-                        end_fill
-                    } else {
-                        self.fill
-                    });
+        let x0 = clamp(bounds.tl.x.floor(), 0.0, width as f32) as u32;
+        let x1 = clamp(bounds.br.x.ceil(), 0.0, width as f32) as u32;
+        let y0 = clamp(bounds.tl.y.floor(), 0.0, height as f32) as u32;
+        let y1 = clamp(bounds.br.y.ceil(), 0.0, height as f32) as u32;
+        if x1 <= x0 || y1 <= y0 {
+            return;
+        }
+
+        let mut raster = Raster::new((x1 - x0) as usize, (y1 - y0) as usize);
+        let offset = Point::new(x0 as f32, y0 as f32);
+        for line in &lines.0 {
+            raster.line(line.0 - offset, line.1 - offset);
+        }
+
+        raster.for_each_coverage(rule, |col, row, coverage| {
+            let x = x0 + col as u32;
+            let y = y0 + row as u32;
+            let src = self.source.sample(x, y);
+            // Apply coverage as a lerp between the untouched destination and the fully-blended
+            // result, so anti-aliasing holds for every blend mode, not just source-over.
+            let dst = self.target.get_pixel(x, y);
+            let blended = self.blend(dst, src);
+            self.target.set_pixel(x, y, lerp_color(dst, blended, coverage));
+        });
+    }
+
+    /// Strokes the active path with the active line width, caps, and joins.
+    ///
+    /// The path is converted into a fillable outline — offset quads for each segment, join geometry
+    /// at interior vertices, and caps at open ends — which is then filled with the coverage
+    /// rasterizer using the non-zero rule so the overlapping pieces union cleanly.
+    pub fn stroke(&mut self) {
+        let contours = self.path.to_contours(self.tolerance);
+        let half_width = self.line_width * 0.5;
+        if half_width <= 0.0 {
+            return;
+        }
+
+        let mut outline = Vec::new();
+        for contour in &contours {
+            self.stroke_contour(contour, half_width, &mut outline);
+        }
+
+        self.rasterize(&Lines(outline), FillRule::NonZero);
+    }
+
+    /// Appends the stroke outline of a single contour as a set of closed convex rings.
+    fn stroke_contour(&self, contour: &Contour, half_width: f32, out: &mut Vec<Line>) {
+        let points = dedupe(&contour.points);
+        let count = points.len();
+
+        // `push_contour` never emits a contour with fewer than two points, so a lone `move_to`
+        // produces no stroke geometry at all.
+        if count < 2 {
+            return;
+        }
+
+        // Offset quad for each segment.
+        for window in points.windows(2) {
+            let (a, b) = (window[0], window[1]);
+            let normal = segment_normal(a, b, half_width);
+            push_ring(vec![a + normal, b + normal, b - normal, a - normal], out);
+        }
+
+        // Joins at every interior vertex (and at the seam for closed contours).
+        for i in 1..(count - 1) {
+            self.stroke_join(points[i - 1], points[i], points[i + 1], half_width, out);
+        }
+        if contour.closed {
+            self.stroke_join(points[count - 2], points[count - 1], points[1], half_width, out);
+        } else {
+            self.stroke_cap(points[1], points[0], half_width, out);
+            self.stroke_cap(points[count - 2], points[count - 1], half_width, out);
+        }
+    }
+
+    /// Appends the join geometry filling the wedge at vertex `v` between segments `u->v` and `v->w`.
+    fn stroke_join(&self, u: Point, v: Point, w: Point, half_width: f32, out: &mut Vec<Line>) {
+        match self.line_join {
+            LineJoin::Round => push_ring(circle(v, half_width, self.tolerance), out),
+            LineJoin::Bevel => self.push_bevel(u, v, w, half_width, out),
+            LineJoin::Miter => {
+                let n0 = segment_normal(u, v, half_width);
+                let n1 = segment_normal(v, w, half_width);
+                let sum = n0 + n1;
+                let sum_len_sq = sum.length_sq();
+                if sum_len_sq < 1e-6 {
+                    self.push_bevel(u, v, w, half_width, out);
+                    return;
                 }
+                // The miter ratio is `1 / cos(theta / 2)`, which equals `2 hw / |n0 + n1|`.
+                let ratio = 2.0 * half_width / sum_len_sq.sqrt();
+                if ratio.abs() > self.miter_limit {
+                    self.push_bevel(u, v, w, half_width, out);
+                    return;
+                }
+                let miter = sum * (ratio * half_width / sum_len_sq.sqrt());
+                push_ring(vec![v, v + n0, v + miter, v + n1], out);
+                push_ring(vec![v, v - n0, v - miter, v - n1], out);
             }
-            y -= 1.0;
         }
     }
 
-    fn fill_pixel(&mut self, x: u32, y: u32, rgba: Color) {
-        self.target.set_pixel(x, y, self.blend(self.target.get_pixel(x, y), rgba));
+    /// Appends a bevel triangle on each side of vertex `v`.
+    fn push_bevel(&self, u: Point, v: Point, w: Point, half_width: f32, out: &mut Vec<Line>) {
+        let n0 = segment_normal(u, v, half_width);
+        let n1 = segment_normal(v, w, half_width);
+        push_ring(vec![v, v + n0, v + n1], out);
+        push_ring(vec![v, v - n0, v - n1], out);
     }
 
-    fn blend(&self, old: Color, new: Color) -> Color {
-        fn comp(ca: f32, cb: f32, aa: f32, ab: f32) -> f32 {
-            (ca * aa + cb * ab * (1.0 - aa)) / (aa + ab * (1.0 - aa))
+    /// Appends the end cap at endpoint `end`, whose incoming segment arrives from `prev`.
+    fn stroke_cap(&self, prev: Point, end: Point, half_width: f32, out: &mut Vec<Line>) {
+        match self.line_cap {
+            LineCap::Butt => {}
+            LineCap::Round => push_ring(circle(end, half_width, self.tolerance), out),
+            LineCap::Square => {
+                let dir = unit(end - prev) * half_width;
+                let normal = segment_normal(prev, end, half_width);
+                push_ring(
+                    vec![end + normal, end + normal + dir, end - normal + dir, end - normal],
+                    out,
+                );
+            }
         }
+    }
+
+    fn blend(&self, old: Color, new: Color) -> Color {
+        let cd = [u2f(old[0]), u2f(old[1]), u2f(old[2])];
+        let ad = u2f(old[3]);
+        let cs = [u2f(new[0]), u2f(new[1]), u2f(new[2])];
+        let as_ = u2f(new[3]);
+
+        // Separable blends feed `B(cb, cs)` into the source color, then composite like `SrcOver`.
+        let src = match self.blend_mode.separable() {
+            Some(b) => [
+                (1.0 - ad) * cs[0] + ad * b(cd[0], cs[0]),
+                (1.0 - ad) * cs[1] + ad * b(cd[1], cs[1]),
+                (1.0 - ad) * cs[2] + ad * b(cd[2], cs[2]),
+            ],
+            None => cs,
+        };
+
+        let (fa, fb) = self.blend_mode.coefficients(as_, ad);
+        // Clamp the premultiplied channels and output alpha to `[0, 1]` before un-premultiplying,
+        // so additive modes saturate (yellow) rather than averaging (olive) when they overflow.
+        let ao = clamp(fa * as_ + fb * ad, 0.0, 1.0);
 
-        let old: [f32; 4] = [u2f(old[0]), u2f(old[1]), u2f(old[2]), u2f(old[3])];
-        let new: [f32; 4] = [u2f(new[0]), u2f(new[1]), u2f(new[2]), u2f(new[3])];
+        let resolve = |i: usize| {
+            if ao > 0.0 {
+                let premult = clamp(fa * as_ * src[i] + fb * ad * cd[i], 0.0, 1.0);
+                f2u(clamp(premult / ao, 0.0, 1.0))
+            } else {
+                0
+            }
+        };
 
-        [
-            f2u(comp(new[0], old[0], new[3], old[3])),
-            f2u(comp(new[1], old[1], new[3], old[3])),
-            f2u(comp(new[2], old[2], new[3], old[3])),
-            f2u(new[3] + (1.0 - new[3]) * old[3]),
-        ]
+        [resolve(0), resolve(1), resolve(2), f2u(ao)]
     }
 }
 
@@ -119,6 +519,141 @@ pub trait Target {
 /// from 0 to 255.
 pub type Color = [u8; 4];
 
+/// How an image pattern samples coordinates that fall outside its bounds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RepeatMode {
+    /// The pattern tiles infinitely.
+    Repeat,
+    /// The pattern tiles, mirroring every other tile.
+    Reflect,
+    /// The edge pixels extend outward.
+    Clamp,
+}
+
+/// A gradient color stop, pairing an offset in `[0, 1]` with a color.
+pub type Stop = (f32, Color);
+
+/// A paint source, queried per pixel during rasterization for the color to blend.
+pub enum Source {
+    /// A single color applied uniformly.
+    Solid(Color),
+    /// Colors interpolated along the `start -> end` axis, projected onto each pixel.
+    Linear {
+        /// The start of the gradient axis.
+        start: (f32, f32),
+        /// The end of the gradient axis.
+        end: (f32, f32),
+        /// The color stops, ordered by ascending offset.
+        stops: Vec<Stop>,
+    },
+    /// Colors interpolated by normalized distance from `center`.
+    Radial {
+        /// The center of the gradient.
+        center: (f32, f32),
+        /// The radius at which the final stop is reached.
+        radius: f32,
+        /// The color stops, ordered by ascending offset.
+        stops: Vec<Stop>,
+    },
+    /// Another target sampled as a repeating image pattern.
+    Pattern {
+        /// The image to sample.
+        image: Box<dyn Target>,
+        /// How coordinates outside the image are resolved.
+        repeat: RepeatMode,
+    },
+}
+
+impl Source {
+    /// Returns the color the source paints at the given pixel coordinate.
+    fn sample(&self, x: u32, y: u32) -> Color {
+        match self {
+            Source::Solid(color) => *color,
+            Source::Linear { start, end, stops } => {
+                let px = x as f32 + 0.5 - start.0;
+                let py = y as f32 + 0.5 - start.1;
+                let dx = end.0 - start.0;
+                let dy = end.1 - start.1;
+                let len_sq = dx * dx + dy * dy;
+                let t = if len_sq > 0.0 {
+                    (px * dx + py * dy) / len_sq
+                } else {
+                    0.0
+                };
+                sample_stops(stops, t)
+            }
+            Source::Radial { center, radius, stops } => {
+                let dx = x as f32 + 0.5 - center.0;
+                let dy = y as f32 + 0.5 - center.1;
+                let t = if *radius > 0.0 {
+                    (dx * dx + dy * dy).sqrt() / radius
+                } else {
+                    0.0
+                };
+                sample_stops(stops, t)
+            }
+            Source::Pattern { image, repeat } => {
+                let w = image.width();
+                let h = image.height();
+                if w == 0 || h == 0 {
+                    return [0, 0, 0, 0];
+                }
+                let sx = wrap(x as i32, w, *repeat);
+                let sy = wrap(y as i32, h, *repeat);
+                image.get_pixel(sx, sy)
+            }
+        }
+    }
+}
+
+/// Interpolates a color from sorted gradient stops at parameter `t`, clamped to `[0, 1]`.
+fn sample_stops(stops: &[Stop], t: f32) -> Color {
+    match stops.first() {
+        None => [0, 0, 0, 0],
+        Some(&(_, first)) => {
+            let t = clamp(t, 0.0, 1.0);
+            let last = stops[stops.len() - 1];
+            if t <= stops[0].0 {
+                return first;
+            }
+            if t >= last.0 {
+                return last.1;
+            }
+
+            for pair in stops.windows(2) {
+                let (o0, c0) = pair[0];
+                let (o1, c1) = pair[1];
+                if t <= o1 {
+                    let span = o1 - o0;
+                    let f = if span > 0.0 { (t - o0) / span } else { 0.0 };
+                    return lerp_color(c0, c1, f);
+                }
+            }
+            last.1
+        }
+    }
+}
+
+/// Linearly interpolates between two colors, component by component.
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let lerp = |ca: u8, cb: u8| f2u(u2f(ca) + (u2f(cb) - u2f(ca)) * t);
+    [lerp(a[0], b[0]), lerp(a[1], b[1]), lerp(a[2], b[2]), lerp(a[3], b[3])]
+}
+
+/// Maps a coordinate onto `[0, extent)` according to the repeat mode.
+fn wrap(v: i32, extent: u32, repeat: RepeatMode) -> u32 {
+    let extent = extent as i32;
+    match repeat {
+        RepeatMode::Clamp => v.max(0).min(extent - 1) as u32,
+        RepeatMode::Repeat => v.rem_euclid(extent) as u32,
+        RepeatMode::Reflect => {
+            let period = 2 * extent;
+            let m = v.rem_euclid(period);
+            (if m < extent { m } else { period - 1 - m }) as u32
+        }
+    }
+}
+
 struct Path {
     start: Point,
     subpaths: Vec<Subpath>,
@@ -146,25 +681,154 @@ impl Path {
         }
     }
 
-    fn to_lines(&self) -> Lines {
+    fn to_lines(&self, tolerance: f32) -> Lines {
         let mut lines = Vec::new();
         let mut active = self.start;
+        let tolerance_sq = tolerance * tolerance;
 
         for subpath in &self.subpaths {
             match *subpath {
                 Subpath::Move(point) => active = point,
-                Subpath::Line(point) => lines.push(Line(mem::replace(&mut active, point), point)),
+                Subpath::Line(point) => {
+                    lines.push(Line(mem::replace(&mut active, point), point));
+                }
+                Subpath::Quad(control, point) => {
+                    flatten_quad(active, control, point, tolerance_sq, 0, &mut lines);
+                    active = point;
+                }
+                Subpath::Cubic(c1, c2, point) => {
+                    flatten_cubic(active, c1, c2, point, tolerance_sq, 0, &mut lines);
+                    active = point;
+                }
             }
         }
 
         Lines(lines)
     }
+
+    fn to_contours(&self, tolerance: f32) -> Vec<Contour> {
+        let mut contours = Vec::new();
+        let mut current = Vec::new();
+        let mut active = self.start;
+
+        for subpath in &self.subpaths {
+            match *subpath {
+                Subpath::Move(point) => {
+                    push_contour(&mut current, &mut contours);
+                    current.push(point);
+                    active = point;
+                }
+                Subpath::Line(point) => {
+                    current.push(point);
+                    active = point;
+                }
+                Subpath::Quad(control, point) => {
+                    let mut lines = Vec::new();
+                    flatten_quad(active, control, point, tolerance * tolerance, 0, &mut lines);
+                    current.extend(lines.iter().map(|line| line.1));
+                    active = point;
+                }
+                Subpath::Cubic(c1, c2, point) => {
+                    let mut lines = Vec::new();
+                    flatten_cubic(active, c1, c2, point, tolerance * tolerance, 0, &mut lines);
+                    current.extend(lines.iter().map(|line| line.1));
+                    active = point;
+                }
+            }
+        }
+
+        push_contour(&mut current, &mut contours);
+        contours
+    }
+}
+
+/// A flattened contour: a polyline that may or may not be closed.
+struct Contour {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+/// Flushes the points accumulated so far into a contour, detecting closure via a coincident first
+/// and last point (as produced by `close_path`).
+fn push_contour(points: &mut Vec<Point>, contours: &mut Vec<Contour>) {
+    if points.len() < 2 {
+        points.clear();
+        return;
+    }
+
+    let taken = mem::take(points);
+    let first = taken[0];
+    let last = taken[taken.len() - 1];
+    let closed = (first - last).length_sq() < 1e-6;
+    contours.push(Contour { points: taken, closed });
 }
 
 #[derive(Debug)]
 enum Subpath {
     Move(Point),
     Line(Point),
+    Quad(Point, Point),
+    Cubic(Point, Point, Point),
+}
+
+/// The deepest the adaptive subdivision will recurse, guarding against degenerate control polygons.
+const MAX_SUBDIVISION: u32 = 16;
+
+/// Tests whether a control point lies within the flatness tolerance of the chord. When the chord is
+/// degenerate (coincident endpoints, as in a closed-loop segment) the perpendicular-distance test
+/// collapses, so fall back to the control point's spread from the start so the curve still flattens.
+fn control_is_flat(p0: Point, control: Point, chord: Point, tolerance_sq: f32) -> bool {
+    let chord_len_sq = chord.length_sq();
+    if chord_len_sq > 1e-12 {
+        cross(control - p0, chord).powi(2) <= tolerance_sq * chord_len_sq
+    } else {
+        (control - p0).length_sq() <= tolerance_sq
+    }
+}
+
+/// Flattens a quadratic Bézier into line segments, subdividing until the control point lies within
+/// the flatness tolerance of the chord.
+fn flatten_quad(p0: Point, c: Point, p1: Point, tolerance_sq: f32, depth: u32, out: &mut Vec<Line>) {
+    let chord = p1 - p0;
+    if depth >= MAX_SUBDIVISION || control_is_flat(p0, c, chord, tolerance_sq) {
+        out.push(Line(p0, p1));
+        return;
+    }
+
+    let p01 = p0.midpoint(c);
+    let c1 = c.midpoint(p1);
+    let mid = p01.midpoint(c1);
+    flatten_quad(p0, p01, mid, tolerance_sq, depth + 1, out);
+    flatten_quad(mid, c1, p1, tolerance_sq, depth + 1, out);
+}
+
+/// Flattens a cubic Bézier into line segments, subdividing via de Casteljau until both control
+/// points lie within the flatness tolerance of the chord.
+fn flatten_cubic(
+    p0: Point,
+    c1: Point,
+    c2: Point,
+    p3: Point,
+    tolerance_sq: f32,
+    depth: u32,
+    out: &mut Vec<Line>,
+) {
+    let chord = p3 - p0;
+    let flat = control_is_flat(p0, c1, chord, tolerance_sq)
+        && control_is_flat(p0, c2, chord, tolerance_sq);
+    if depth >= MAX_SUBDIVISION || flat {
+        out.push(Line(p0, p3));
+        return;
+    }
+
+    let p01 = p0.midpoint(c1);
+    let p12 = c1.midpoint(c2);
+    let p23 = c2.midpoint(p3);
+    let p012 = p01.midpoint(p12);
+    let p123 = p12.midpoint(p23);
+    let mid = p012.midpoint(p123);
+    flatten_cubic(p0, p01, p012, mid, tolerance_sq, depth + 1, out);
+    flatten_cubic(mid, p123, p23, p3, tolerance_sq, depth + 1, out);
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -177,6 +841,107 @@ impl Point {
     fn new(x: f32, y: f32) -> Point {
         Point { x, y }
     }
+
+    fn midpoint(self, other: Point) -> Point {
+        Point::new(0.5 * (self.x + other.x), 0.5 * (self.y + other.y))
+    }
+
+    fn length_sq(self) -> f32 {
+        self.x * self.x + self.y * self.y
+    }
+}
+
+impl std::ops::Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl std::ops::Mul<f32> for Point {
+    type Output = Point;
+
+    fn mul(self, scalar: f32) -> Point {
+        Point::new(self.x * scalar, self.y * scalar)
+    }
+}
+
+fn cross(a: Point, b: Point) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn dot(a: Point, b: Point) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+/// Returns the unit vector in the direction of `v`, or a zero vector if `v` is degenerate.
+fn unit(v: Point) -> Point {
+    let length = v.length_sq().sqrt();
+    if length > 0.0 {
+        v * (1.0 / length)
+    } else {
+        Point::new(0.0, 0.0)
+    }
+}
+
+/// Returns the left-hand normal of the segment `a -> b`, scaled to `length`.
+fn segment_normal(a: Point, b: Point, length: f32) -> Point {
+    let dir = unit(b - a);
+    Point::new(-dir.y, dir.x) * length
+}
+
+/// Tessellates a circle into a closed polygon whose chords stay within `tolerance` of the arc.
+///
+/// The vertices wind in the same direction as the offset segment quads so every stroke ring shares
+/// a winding sign and overlapping rings union under the non-zero rule rather than cancelling.
+fn circle(center: Point, radius: f32, tolerance: f32) -> Vec<Point> {
+    let steps = if radius <= tolerance {
+        8
+    } else {
+        let max_angle = 2.0 * (1.0 - tolerance / radius).acos();
+        ((2.0 * f32::consts::PI / max_angle).ceil() as usize).max(8)
+    };
+
+    let mut points = Vec::with_capacity(steps);
+    for i in 0..steps {
+        let theta = -2.0 * f32::consts::PI * (i as f32) / (steps as f32);
+        points.push(Point::new(
+            center.x + radius * theta.cos(),
+            center.y + radius * theta.sin(),
+        ));
+    }
+    points
+}
+
+/// Drops consecutive coincident points so segment directions stay well defined.
+fn dedupe(points: &[Point]) -> Vec<Point> {
+    let mut out: Vec<Point> = Vec::with_capacity(points.len());
+    for &point in points {
+        if out.last().is_none_or(|last| (*last - point).length_sq() >= 1e-12) {
+            out.push(point);
+        }
+    }
+    out
+}
+
+/// Appends a closed polygon as a run of edges feeding the coverage rasterizer.
+fn push_ring(ring: Vec<Point>, out: &mut Vec<Line>) {
+    let count = ring.len();
+    if count < 2 {
+        return;
+    }
+    for i in 0..count {
+        out.push(Line(ring[i], ring[(i + 1) % count]));
+    }
+}
+
+impl std::ops::Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
 }
 
 #[derive(Debug)]
@@ -206,24 +971,138 @@ impl Lines {
 
         bounds
     }
+}
+
+struct Rect {
+    tl: Point,
+    br: Point,
+}
+
+/// A signed-area coverage accumulator.
+///
+/// Edges deposit a partial trapezoidal area into their own pixel and a unit cover into the column
+/// to their right, signed by the edge's vertical direction. A left-to-right prefix sum over each
+/// row then recovers the fractional coverage in `[0, 1]` for every pixel.
+struct Raster {
+    width: usize,
+    height: usize,
+    // One extra column absorbs the unit cover deposited to the right of the rightmost edge.
+    area: Vec<f32>,
+}
+
+impl Raster {
+    fn new(width: usize, height: usize) -> Raster {
+        Raster {
+            width,
+            height,
+            // The `+ 1` absorbs a unit cover deposited just past the final column.
+            area: vec![0.0; (width + 1) * height + 1],
+        }
+    }
 
-    fn x_intersections(&self, y: f32) -> Vec<f32> {
-        let mut xs = Vec::new();
+    /// Accumulates a single edge, walking every scanline row it crosses.
+    fn line(&mut self, p0: Point, p1: Point) {
+        let (dir, top, bottom) = if p0.y < p1.y {
+            (1.0, p0, p1)
+        } else {
+            (-1.0, p1, p0)
+        };
+        if top.y == bottom.y {
+            return;
+        }
 
-        for line in &self.0 {
-            if (line.0.y < y && line.1.y >= y) || (line.1.y < y && line.0.y >= y) {
-                xs.push(line.0.x + (y - line.0.y) / (line.1.y - line.0.y) * (line.1.x - line.0.x));
+        let dxdy = (bottom.x - top.x) / (bottom.y - top.y);
+        let mut x = top.x;
+        if top.y < 0.0 {
+            x -= top.y * dxdy;
+        }
+        let y_start = top.y.max(0.0);
+        let y_end = bottom.y.min(self.height as f32);
+
+        let mut row = y_start as usize;
+        while (row as f32) < y_end {
+            let stride = (self.width + 1) * row;
+            let dy = ((row + 1) as f32).min(y_end) - (row as f32).max(y_start);
+            let x_next = x + dxdy * dy;
+            let d = dy * dir;
+
+            // Clip the entering/leaving x to the raster so an off-canvas edge folds its cover into
+            // the boundary column rather than the deposit indices running off the clamped columns.
+            let w = self.width as f32;
+            let x_in = clamp(x, 0.0, w);
+            let x_out = clamp(x_next, 0.0, w);
+
+            // `x_in` enters the row and `x_out` leaves it; order them so the trapezoid area is taken
+            // over the covered columns left-to-right.
+            let (x_left, x_right) = if x_in < x_out { (x_in, x_out) } else { (x_out, x_in) };
+            let left_floor = x_left.floor();
+            let left_col = left_floor as usize;
+            let right_col = x_right.ceil() as usize;
+
+            if right_col <= left_col + 1 {
+                // The edge stays within a single pixel column for this row. The area it leaves
+                // uncovered within that pixel is proportional to its mean x offset into the column.
+                let xmf = 0.5 * (x_in + x_out) - left_floor;
+                self.area[stride + left_col] += d * (1.0 - xmf);
+                // Cap the cover index at the sentinel so it never spills into the next row.
+                self.area[stride + (left_col + 1).min(self.width)] += d * xmf;
+            } else {
+                // The edge spans several columns; distribute the area as a trapezoid sliced at each
+                // column boundary, depositing the leftover cover into the column to the right.
+                let s = (x_right - x_left).recip();
+                let x0f = x_left - left_floor;
+                let one_minus_x0f = 1.0 - x0f;
+                let a0 = 0.5 * s * one_minus_x0f * one_minus_x0f;
+                let x1f = x_right - right_col as f32 + 1.0;
+                let am = 0.5 * s * x1f * x1f;
+
+                self.area[stride + left_col] += d * a0;
+                if right_col == left_col + 2 {
+                    self.area[stride + left_col + 1] += d * (1.0 - a0 - am);
+                } else {
+                    let a1 = s * (1.5 - x0f);
+                    self.area[stride + left_col + 1] += d * (a1 - a0);
+                    for col in (left_col + 2)..(right_col - 1) {
+                        self.area[stride + col] += d * s;
+                    }
+                    let a2 = a1 + (right_col - left_col - 3) as f32 * s;
+                    self.area[stride + right_col - 1] += d * (1.0 - a2 - am);
+                }
+                self.area[stride + right_col] += d * am;
             }
+
+            x = x_next;
+            row += 1;
         }
+    }
 
-        xs.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal));
-        xs
+    /// Sweeps each row, invoking `f(col, row, coverage)` for every covered pixel.
+    ///
+    /// The running prefix sum is the (fractional) winding number at each column; the active
+    /// `FillRule` maps it to a coverage in `[0, 1]`.
+    fn for_each_coverage<F: FnMut(usize, usize, f32)>(&self, rule: FillRule, mut f: F) {
+        for row in 0..self.height {
+            let stride = (self.width + 1) * row;
+            let mut acc = 0.0;
+            for col in 0..self.width {
+                acc += self.area[stride + col];
+                let coverage = match rule {
+                    FillRule::NonZero => acc.abs().min(1.0),
+                    FillRule::EvenOdd => {
+                        let folded = acc.abs() % 2.0;
+                        if folded > 1.0 { 2.0 - folded } else { folded }
+                    }
+                };
+                if coverage > 0.0 {
+                    f(col, row, coverage);
+                }
+            }
+        }
     }
 }
 
-struct Rect {
-    tl: Point,
-    br: Point,
+fn clamp(v: f32, min: f32, max: f32) -> f32 {
+    v.max(min).min(max)
 }
 
 fn u2f(v: u8) -> f32 {